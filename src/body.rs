@@ -0,0 +1,72 @@
+//! Shared body-reading helper for [`crate::Json`] and [`crate::Form`].
+//!
+//! Enforces a size limit while the body is being streamed in, rather than
+//! buffering the whole thing first and only checking its length afterwards —
+//! otherwise a client that lies about (or omits) `Content-Length` could have
+//! an arbitrarily large chunked body fully read into memory before the limit
+//! ever kicks in.
+
+use actix_web::{dev::Payload, error::PayloadError, web::BytesMut};
+use futures_util::StreamExt;
+
+/// Reads `payload` to completion, failing as soon as the cumulative size
+/// exceeds `limit` instead of after the whole body has been buffered.
+pub(crate) async fn collect_limited(
+    mut payload: Payload,
+    limit: usize,
+) -> Result<actix_web::web::Bytes, BodyLimitError> {
+    let mut body = BytesMut::new();
+
+    while let Some(chunk) = payload.next().await {
+        let chunk = chunk.map_err(BodyLimitError::Payload)?;
+
+        if body.len() + chunk.len() > limit {
+            return Err(BodyLimitError::Overflow {
+                limit,
+                length: body.len() + chunk.len(),
+            });
+        }
+
+        body.extend_from_slice(&chunk);
+    }
+
+    Ok(body.freeze())
+}
+
+pub(crate) enum BodyLimitError {
+    /// The underlying payload stream errored.
+    Payload(PayloadError),
+    /// The body exceeded `limit` before it was fully read.
+    Overflow { limit: usize, length: usize },
+}
+
+#[cfg(test)]
+mod tests {
+    use actix_web::test::TestRequest;
+
+    use super::*;
+
+    #[actix_web::test]
+    async fn accepts_body_exactly_at_limit() {
+        let (_req, payload) = TestRequest::default().set_payload("12345").to_http_parts();
+
+        let data = collect_limited(payload, 5).await.unwrap();
+
+        assert_eq!(data.as_ref(), b"12345");
+    }
+
+    #[actix_web::test]
+    async fn rejects_body_one_byte_over_limit() {
+        let (_req, payload) = TestRequest::default().set_payload("123456").to_http_parts();
+
+        let err = collect_limited(payload, 5).await.unwrap_err();
+
+        assert!(matches!(
+            err,
+            BodyLimitError::Overflow {
+                limit: 5,
+                length: 6
+            }
+        ));
+    }
+}
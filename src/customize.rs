@@ -0,0 +1,93 @@
+use actix_web::{
+    HttpRequest, HttpResponse, Responder,
+    body::EitherBody,
+    http::{
+        StatusCode,
+        header::{HeaderName, HeaderValue, TryIntoHeaderPair},
+    },
+};
+
+/// Wraps a [`Responder`] to override its status code and/or add extra
+/// headers, without giving up how the body itself gets serialized.
+///
+/// Built through [`Json::customize`](crate::Json::customize):
+///
+/// ```ignore
+/// async fn create() -> facet_actix::CustomizeResponder<facet_actix::Json<Item>> {
+///     facet_actix::Json(item).customize().with_status(StatusCode::CREATED)
+/// }
+/// ```
+pub struct CustomizeResponder<R> {
+    responder: R,
+    status: Option<StatusCode>,
+    insert_headers: Vec<(HeaderName, HeaderValue)>,
+    append_headers: Vec<(HeaderName, HeaderValue)>,
+}
+
+impl<R: Responder> CustomizeResponder<R> {
+    pub(crate) fn new(responder: R) -> Self {
+        CustomizeResponder {
+            responder,
+            status: None,
+            insert_headers: Vec::new(),
+            append_headers: Vec::new(),
+        }
+    }
+
+    /// Set the response status code, overriding the inner responder's own.
+    pub fn with_status(mut self, status: StatusCode) -> Self {
+        self.status = Some(status);
+        self
+    }
+
+    /// Insert a header, replacing any existing header with the same name.
+    pub fn insert_header(mut self, header: impl TryIntoHeaderPair) -> Self {
+        match header.try_into_pair() {
+            Ok((key, value)) => self.insert_headers.push((key, value)),
+            Err(err) => log::error!("Invalid header passed to `insert_header`: {err}"),
+        }
+        self
+    }
+
+    /// Append a header, keeping any existing header with the same name.
+    pub fn append_header(mut self, header: impl TryIntoHeaderPair) -> Self {
+        match header.try_into_pair() {
+            Ok((key, value)) => self.append_headers.push((key, value)),
+            Err(err) => log::error!("Invalid header passed to `append_header`: {err}"),
+        }
+        self
+    }
+}
+
+impl<R> Responder for CustomizeResponder<R>
+where
+    R: Responder<Body = EitherBody<String>>,
+{
+    type Body = EitherBody<String>;
+
+    fn respond_to(self, req: &HttpRequest) -> HttpResponse<Self::Body> {
+        let mut res = self.responder.respond_to(req);
+
+        // The inner responder already failed — e.g. `Json`'s `Responder` impl
+        // hit a serialize error and fell back to its `Right` error body.
+        // Applying the status/header overrides on top of that would clobber
+        // the error response, so leave it alone.
+        if matches!(res.body(), EitherBody::Right { .. }) {
+            return res;
+        }
+
+        if let Some(status) = self.status {
+            *res.status_mut() = status;
+        }
+
+        for (key, value) in self.insert_headers {
+            res.headers_mut().insert(key, value);
+        }
+
+        for (key, value) in self.append_headers {
+            res.headers_mut().append(key, value);
+        }
+
+        res
+    }
+}
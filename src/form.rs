@@ -1,19 +1,34 @@
 use core::fmt;
-use std::{
-    marker::PhantomData,
-    ops,
-    pin::Pin,
-    task::{Context, Poll, ready},
-};
+use std::{future::Future, ops, pin::Pin, rc::Rc};
 
 use actix_web::{
-    FromRequest, HttpRequest, ResponseError,
-    http::{StatusCode, header::CONTENT_TYPE},
-    mime::{APPLICATION_WWW_FORM_URLENCODED, MULTIPART_FORM_DATA},
-    web::Bytes,
+    FromRequest, HttpRequest, HttpResponse, Responder, ResponseError,
+    body::EitherBody,
+    http::{
+        StatusCode,
+        header::{CONTENT_LENGTH, CONTENT_TYPE},
+    },
+    mime,
 };
 use facet::Facet;
+use facet_format::SerializeError;
+use facet_urlencoded::UrlEncodedSerializeError;
+
+use crate::body::{self, BodyLimitError};
+use crate::multipart;
 
+/// A boxed, pinned future, used instead of a hand-rolled `Future` impl so
+/// extraction can just be written as a straight-line `async` block.
+type BoxFuture<T> = Pin<Box<dyn Future<Output = T>>>;
+
+/// Extracts a `T` out of an `application/x-www-form-urlencoded` or
+/// `multipart/form-data` request body, deserialized through
+/// `facet_urlencoded`.
+///
+/// Multipart file parts (images, PDFs, any non-text upload) are not
+/// supported: a part's body is decoded as UTF-8 so it can be re-encoded and
+/// run through the same deserializer as a urlencoded body, so a binary part
+/// always rejects the whole request with [`FormRejection::InvalidUtf8`].
 #[derive(Debug, facet::Facet)]
 #[facet(transparent)]
 pub struct Form<T>(pub T);
@@ -45,6 +60,64 @@ impl<T: fmt::Display> fmt::Display for Form<T> {
     }
 }
 
+/// Default body size limit applied to [`Form`] extraction when no
+/// [`FormConfig`] is registered (16 KiB).
+const DEFAULT_LIMIT: usize = 16_384;
+
+/// Extractor configuration for [`Form`].
+///
+/// Register one with `App::app_data`/`Resource::app_data` to override the
+/// default body size limit, relax the `Content-Type` check, or take full
+/// control of how rejections are turned into an `actix_web::Error`:
+///
+/// ```ignore
+/// App::new().app_data(FormConfig::default().limit(4096))
+/// ```
+#[derive(Clone)]
+pub struct FormConfig {
+    limit: usize,
+    content_type: Option<Rc<dyn Fn(&mime::Mime) -> bool>>,
+    error_handler: Option<Rc<dyn Fn(FormRejection, &HttpRequest) -> actix_web::Error>>,
+}
+
+impl FormConfig {
+    /// Set the maximum allowed body size, in bytes.
+    pub fn limit(mut self, limit: usize) -> Self {
+        self.limit = limit;
+        self
+    }
+
+    /// Relax/override the default `Content-Type` check (urlencoded or
+    /// multipart) with a custom predicate.
+    pub fn content_type<F>(mut self, predicate: F) -> Self
+    where
+        F: Fn(&mime::Mime) -> bool + 'static,
+    {
+        self.content_type = Some(Rc::new(predicate));
+        self
+    }
+
+    /// Override how a [`FormRejection`] is turned into the `actix_web::Error`
+    /// returned to the caller.
+    pub fn error_handler<F>(mut self, handler: F) -> Self
+    where
+        F: Fn(FormRejection, &HttpRequest) -> actix_web::Error + 'static,
+    {
+        self.error_handler = Some(Rc::new(handler));
+        self
+    }
+}
+
+impl Default for FormConfig {
+    fn default() -> Self {
+        FormConfig {
+            limit: DEFAULT_LIMIT,
+            content_type: None,
+            error_handler: None,
+        }
+    }
+}
+
 #[derive(Debug)]
 pub enum FormRejection {
     /// Failed to read the request body.
@@ -55,6 +128,18 @@ pub enum FormRejection {
     MissingContentType,
     /// Invalid `Content-Type` header (not x-www-form-urlencoded).
     InvalidContentType,
+    /// The body (or its announced `Content-Length`) exceeds the configured
+    /// [`FormConfig::limit`].
+    Overflow { limit: usize, length: usize },
+    /// `multipart/form-data` body with no (or an empty) `boundary` parameter.
+    MissingBoundary,
+    /// A multipart part was missing its headers/body separator or its
+    /// `Content-Disposition: form-data; name="..."` header.
+    MalformedMultipart,
+    /// The body is not valid UTF-8 — either a urlencoded body isn't
+    /// ASCII/UTF-8, or a multipart part holds binary content (an image, a
+    /// PDF, ...), which this extractor doesn't support.
+    InvalidUtf8,
 }
 
 impl fmt::Display for FormRejection {
@@ -75,6 +160,18 @@ impl fmt::Display for FormRejection {
                     "Invalid `Content-Type` header: expected `x-www-form-urlencoded`"
                 )
             }
+            FormRejection::Overflow { limit, length } => {
+                write!(f, "Form payload ({length} bytes) is larger than allowed (limit: {limit} bytes)")
+            }
+            FormRejection::MissingBoundary => {
+                write!(f, "Missing or empty `boundary` parameter on multipart `Content-Type`")
+            }
+            FormRejection::MalformedMultipart => {
+                write!(f, "Malformed multipart part framing")
+            }
+            FormRejection::InvalidUtf8 => {
+                write!(f, "Form body is not valid UTF-8")
+            }
         }
     }
 }
@@ -87,75 +184,178 @@ impl ResponseError for FormRejection {
             FormRejection::MissingContentType | FormRejection::InvalidContentType => {
                 StatusCode::UNSUPPORTED_MEDIA_TYPE
             }
+            FormRejection::Overflow { .. } => StatusCode::PAYLOAD_TOO_LARGE,
+            FormRejection::MissingBoundary | FormRejection::MalformedMultipart => {
+                StatusCode::BAD_REQUEST
+            }
+            FormRejection::InvalidUtf8 => StatusCode::BAD_REQUEST,
         }
     }
 }
 
-impl<T: Facet<'static>> actix_web::FromRequest for Form<T> {
-    type Error = FormRejection;
-    type Future = FormExtractFut<T>;
-
-    fn from_request(
-        req: &actix_web::HttpRequest,
-        payload: &mut actix_web::dev::Payload,
-    ) -> Self::Future {
-        FormExtractFut {
-            req: Some(req.clone()),
-            bytes: Bytes::from_request(req, payload),
-            marker: PhantomData,
+fn check_content_type(req: &HttpRequest, config: &FormConfig) -> Result<(), FormRejection> {
+    let Some(ct) = req.headers().get(CONTENT_TYPE) else {
+        return Err(FormRejection::MissingContentType);
+    };
+
+    // Parsed through `mime` rather than compared byte-for-byte so a
+    // `charset=utf-8` (or other) parameter doesn't trip a false rejection.
+    let Ok(mime) = ct.to_str().unwrap_or_default().parse::<mime::Mime>() else {
+        return Err(FormRejection::InvalidContentType);
+    };
+
+    let valid = match &config.content_type {
+        Some(predicate) => predicate(&mime),
+        None => {
+            (mime.type_() == mime::APPLICATION && mime.subtype() == mime::WWW_FORM_URLENCODED)
+                || mime.type_() == mime::MULTIPART
         }
+    };
+
+    if valid {
+        Ok(())
+    } else {
+        Err(FormRejection::InvalidContentType)
+    }
+}
+
+fn check_announced_length(req: &HttpRequest, config: &FormConfig) -> Result<(), FormRejection> {
+    let Some(length) = req
+        .headers()
+        .get(CONTENT_LENGTH)
+        .and_then(|len| len.to_str().ok())
+        .and_then(|len| len.parse::<usize>().ok())
+    else {
+        return Ok(());
+    };
+
+    if length > config.limit {
+        Err(FormRejection::Overflow {
+            limit: config.limit,
+            length,
+        })
+    } else {
+        Ok(())
     }
 }
 
-pub struct FormExtractFut<T: Facet<'static>> {
-    req: Option<HttpRequest>,
-    bytes: <Bytes as FromRequest>::Future,
-    marker: PhantomData<T>,
+fn map_rejection(
+    rejection: FormRejection,
+    req: &HttpRequest,
+    config: &FormConfig,
+) -> actix_web::Error {
+    match &config.error_handler {
+        Some(handler) => handler(rejection, req),
+        None => rejection.into(),
+    }
 }
 
-impl<T: Facet<'static>> Unpin for FormExtractFut<T> {}
+/// Which body format to parse, decided once up front from the raw
+/// `Content-Type` header (independent of [`FormConfig::content_type`], which
+/// only governs whether the header is *accepted*).
+enum FormBodyKind {
+    Urlencoded,
+    Multipart { boundary: String },
+}
 
-impl<T: Facet<'static>> Future for FormExtractFut<T> {
-    type Output = Result<Form<T>, FormRejection>;
+fn detect_body_kind(req: &HttpRequest) -> Result<FormBodyKind, FormRejection> {
+    let ct = req
+        .headers()
+        .get(CONTENT_TYPE)
+        .ok_or(FormRejection::MissingContentType)?
+        .to_str()
+        .map_err(|_| FormRejection::InvalidContentType)?;
 
-    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
-        let FormExtractFut { req, bytes, .. } = self.get_mut();
+    let mime: mime::Mime = ct.parse().map_err(|_| FormRejection::InvalidContentType)?;
 
-        if let Some(req) = req.take() {
-            match req.headers().get(CONTENT_TYPE) {
-                Some(ct)
-                    if !ct
-                        .to_str()
-                        // TODO: remove unwrap
-                        .unwrap()
-                        .starts_with(APPLICATION_WWW_FORM_URLENCODED.as_ref())
-                        && !ct
-                            .to_str()
-                            .unwrap()
-                            .starts_with(MULTIPART_FORM_DATA.as_ref()) =>
-                {
-                    Err(FormRejection::InvalidContentType)?
-                }
-                Some(_) => (),
-                None => Err(FormRejection::MissingContentType)?,
-            }
-        }
+    if mime.type_() == mime::MULTIPART {
+        let boundary = multipart::parse_boundary(ct)?;
+        Ok(FormBodyKind::Multipart {
+            boundary: boundary.to_owned(),
+        })
+    } else {
+        Ok(FormBodyKind::Urlencoded)
+    }
+}
+
+impl<T: Facet<'static>> actix_web::FromRequest for Form<T> {
+    type Error = actix_web::Error;
+    type Future = BoxFuture<Result<Form<T>, actix_web::Error>>;
 
-        let fut = Pin::new(bytes);
+    fn from_request(req: &HttpRequest, payload: &mut actix_web::dev::Payload) -> Self::Future {
+        let req = req.clone();
+        let config = req.app_data::<FormConfig>().cloned().unwrap_or_default();
+        let payload = payload.take();
 
-        let res = ready!(fut.poll(cx));
+        Box::pin(async move {
+            let extract = async {
+                let kind = check_content_type(&req, &config)
+                    .and_then(|()| check_announced_length(&req, &config))
+                    .and_then(|()| detect_body_kind(&req))?;
 
-        let res = match res {
-            Err(err) => Err(FormRejection::Body(err)),
-            Ok(data) => {
-                match facet_urlencoded::from_str_owned::<T>(str::from_utf8(data.as_ref()).unwrap())
-                {
-                    Ok(data) => Ok(Form(data)),
-                    Err(e) => Err(FormRejection::Deserialize(e))?,
+                let data = body::collect_limited(payload, config.limit)
+                    .await
+                    .map_err(|err| match err {
+                        BodyLimitError::Payload(err) => FormRejection::Body(err.into()),
+                        BodyLimitError::Overflow { limit, length } => {
+                            FormRejection::Overflow { limit, length }
+                        }
+                    })?;
+
+                match kind {
+                    FormBodyKind::Urlencoded => {
+                        let data =
+                            str::from_utf8(data.as_ref()).map_err(|_| FormRejection::InvalidUtf8)?;
+                        facet_urlencoded::from_str_owned::<T>(data)
+                            .map(Form)
+                            .map_err(FormRejection::Deserialize)
+                    }
+                    FormBodyKind::Multipart { boundary } => {
+                        let pairs = multipart::parse_parts(&data, &boundary)?;
+                        let encoded = multipart::pairs_to_urlencoded(&pairs);
+                        facet_urlencoded::from_str_owned::<T>(&encoded)
+                            .map(Form)
+                            .map_err(FormRejection::Deserialize)
+                    }
                 }
+            };
+
+            extract.await.map_err(|rejection| map_rejection(rejection, &req, &config))
+        })
+    }
+}
+
+impl<'a, T: Facet<'a>> Responder for Form<T> {
+    type Body = EitherBody<String>;
+
+    fn respond_to(self, _: &HttpRequest) -> HttpResponse<Self::Body> {
+        match facet_urlencoded::to_string(&self.0) {
+            Ok(body) => match HttpResponse::Ok()
+                .content_type(mime::APPLICATION_WWW_FORM_URLENCODED)
+                .message_body(body)
+            {
+                Ok(res) => res.map_into_left_body(),
+                Err(err) => HttpResponse::from_error(err).map_into_right_body(),
+            },
+
+            Err(err) => {
+                HttpResponse::from_error(SerializeErrorToActixError(err)).map_into_right_body()
             }
-        };
+        }
+    }
+}
 
-        Poll::Ready(res)
+#[derive(Debug)]
+struct SerializeErrorToActixError(pub SerializeError<UrlEncodedSerializeError>);
+
+impl fmt::Display for SerializeErrorToActixError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        self.0.fmt(f)
+    }
+}
+
+impl actix_web::ResponseError for SerializeErrorToActixError {
+    fn status_code(&self) -> StatusCode {
+        StatusCode::INTERNAL_SERVER_ERROR
     }
 }
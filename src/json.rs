@@ -1,22 +1,25 @@
 use core::fmt;
-use std::{
-    marker::PhantomData,
-    ops,
-    pin::Pin,
-    task::{Context, Poll, ready},
-};
+use std::{future::Future, ops, pin::Pin, rc::Rc};
 
 use actix_web::{
     FromRequest, HttpRequest, HttpResponse, Responder, ResponseError,
     body::EitherBody,
-    http::{StatusCode, header::CONTENT_TYPE},
-    mime::{self, APPLICATION_JSON},
-    web::Bytes,
+    http::{
+        StatusCode,
+        header::{CONTENT_LENGTH, CONTENT_TYPE},
+    },
+    mime,
 };
 use facet::Facet;
 use facet_format::SerializeError;
 use facet_json::{DeserializeError, JsonSerializeError};
 
+use crate::body::{self, BodyLimitError};
+
+/// A boxed, pinned future, used instead of a hand-rolled `Future` impl so
+/// extraction can just be written as a straight-line `async` block.
+type BoxFuture<T> = Pin<Box<dyn Future<Output = T>>>;
+
 #[derive(Debug, facet::Facet)]
 #[facet(transparent)]
 pub struct Json<T>(pub T);
@@ -48,6 +51,68 @@ impl<T: fmt::Display> fmt::Display for Json<T> {
     }
 }
 
+/// Default body size limit applied to [`Json`] extraction when no
+/// [`JsonConfig`] is registered (2 MiB).
+const DEFAULT_LIMIT: usize = 2_097_152;
+
+/// Extractor configuration for [`Json`].
+///
+/// Register one with `App::app_data`/`Resource::app_data` to override the
+/// default body size limit, relax the `Content-Type` check, or take full
+/// control of how rejections are turned into an `actix_web::Error`:
+///
+/// ```ignore
+/// App::new().app_data(
+///     JsonConfig::default()
+///         .limit(4096)
+///         .content_type(|mime| mime.subtype() == mime::JSON),
+/// )
+/// ```
+#[derive(Clone)]
+pub struct JsonConfig {
+    limit: usize,
+    content_type: Option<Rc<dyn Fn(&mime::Mime) -> bool>>,
+    error_handler: Option<Rc<dyn Fn(JsonRejection, &HttpRequest) -> actix_web::Error>>,
+}
+
+impl JsonConfig {
+    /// Set the maximum allowed body size, in bytes.
+    pub fn limit(mut self, limit: usize) -> Self {
+        self.limit = limit;
+        self
+    }
+
+    /// Relax/override the default exact `application/json` `Content-Type`
+    /// check with a custom predicate.
+    pub fn content_type<F>(mut self, predicate: F) -> Self
+    where
+        F: Fn(&mime::Mime) -> bool + 'static,
+    {
+        self.content_type = Some(Rc::new(predicate));
+        self
+    }
+
+    /// Override how a [`JsonRejection`] is turned into the `actix_web::Error`
+    /// returned to the caller.
+    pub fn error_handler<F>(mut self, handler: F) -> Self
+    where
+        F: Fn(JsonRejection, &HttpRequest) -> actix_web::Error + 'static,
+    {
+        self.error_handler = Some(Rc::new(handler));
+        self
+    }
+}
+
+impl Default for JsonConfig {
+    fn default() -> Self {
+        JsonConfig {
+            limit: DEFAULT_LIMIT,
+            content_type: None,
+            error_handler: None,
+        }
+    }
+}
+
 #[derive(Debug)]
 pub enum JsonRejection {
     /// Failed to read the request body.
@@ -58,6 +123,9 @@ pub enum JsonRejection {
     MissingContentType,
     /// Invalid `Content-Type` header (not application/json).
     InvalidContentType,
+    /// The body (or its announced `Content-Length`) exceeds the configured
+    /// [`JsonConfig::limit`].
+    Overflow { limit: usize, length: usize },
 }
 
 impl fmt::Display for JsonRejection {
@@ -78,6 +146,9 @@ impl fmt::Display for JsonRejection {
                     "Invalid `Content-Type` header: expected `application/json`"
                 )
             }
+            JsonRejection::Overflow { limit, length } => {
+                write!(f, "JSON payload ({length} bytes) is larger than allowed (limit: {limit} bytes)")
+            }
         }
     }
 }
@@ -90,63 +161,104 @@ impl ResponseError for JsonRejection {
             JsonRejection::MissingContentType | JsonRejection::InvalidContentType => {
                 StatusCode::UNSUPPORTED_MEDIA_TYPE
             }
+            JsonRejection::Overflow { .. } => StatusCode::PAYLOAD_TOO_LARGE,
         }
     }
 }
 
-impl<T: Facet<'static>> actix_web::FromRequest for Json<T> {
-    type Error = JsonRejection;
-    type Future = JsonExtractFut<T>;
-
-    fn from_request(
-        req: &actix_web::HttpRequest,
-        payload: &mut actix_web::dev::Payload,
-    ) -> Self::Future {
-        JsonExtractFut {
-            req: Some(req.clone()),
-            bytes: Bytes::from_request(req, payload),
-            marker: PhantomData,
-        }
+fn check_content_type(req: &HttpRequest, config: &JsonConfig) -> Result<(), JsonRejection> {
+    let Some(ct) = req.headers().get(CONTENT_TYPE) else {
+        return Err(JsonRejection::MissingContentType);
+    };
+
+    // Parsed through `mime` rather than compared byte-for-byte so a
+    // `charset=utf-8` (or other) parameter doesn't trip a false rejection.
+    let Ok(mime) = ct.to_str().unwrap_or_default().parse::<mime::Mime>() else {
+        return Err(JsonRejection::InvalidContentType);
+    };
+
+    let valid = match &config.content_type {
+        Some(predicate) => predicate(&mime),
+        None => mime.type_() == mime::APPLICATION && mime.subtype() == mime::JSON,
+    };
+
+    if valid {
+        Ok(())
+    } else {
+        Err(JsonRejection::InvalidContentType)
     }
 }
 
-pub struct JsonExtractFut<T: Facet<'static>> {
-    req: Option<HttpRequest>,
-    bytes: <Bytes as FromRequest>::Future,
-    marker: PhantomData<T>,
+fn check_announced_length(req: &HttpRequest, config: &JsonConfig) -> Result<(), JsonRejection> {
+    let Some(length) = req
+        .headers()
+        .get(CONTENT_LENGTH)
+        .and_then(|len| len.to_str().ok())
+        .and_then(|len| len.parse::<usize>().ok())
+    else {
+        return Ok(());
+    };
+
+    if length > config.limit {
+        Err(JsonRejection::Overflow {
+            limit: config.limit,
+            length,
+        })
+    } else {
+        Ok(())
+    }
 }
 
-impl<T: Facet<'static>> Unpin for JsonExtractFut<T> {}
+fn map_rejection(
+    rejection: JsonRejection,
+    req: &HttpRequest,
+    config: &JsonConfig,
+) -> actix_web::Error {
+    match &config.error_handler {
+        Some(handler) => handler(rejection, req),
+        None => rejection.into(),
+    }
+}
 
-impl<T: Facet<'static>> Future for JsonExtractFut<T> {
-    type Output = Result<Json<T>, JsonRejection>;
+impl<T: Facet<'static>> actix_web::FromRequest for Json<T> {
+    type Error = actix_web::Error;
+    type Future = BoxFuture<Result<Json<T>, actix_web::Error>>;
 
-    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
-        let JsonExtractFut { req, bytes, .. } = self.get_mut();
+    fn from_request(req: &HttpRequest, payload: &mut actix_web::dev::Payload) -> Self::Future {
+        let req = req.clone();
+        let config = req.app_data::<JsonConfig>().cloned().unwrap_or_default();
+        let payload = payload.take();
 
-        if let Some(req) = req.take() {
-            match req.headers().get(CONTENT_TYPE) {
-                Some(ct) if ct != APPLICATION_JSON.as_ref() => {
-                    Err(JsonRejection::InvalidContentType)?
-                }
-                Some(_) => (),
-                None => Err(JsonRejection::MissingContentType)?,
-            }
-        }
+        Box::pin(async move {
+            let extract = async {
+                check_content_type(&req, &config)
+                    .and_then(|()| check_announced_length(&req, &config))?;
 
-        let fut = Pin::new(bytes);
+                let data = body::collect_limited(payload, config.limit)
+                    .await
+                    .map_err(|err| match err {
+                        BodyLimitError::Payload(err) => JsonRejection::Body(err.into()),
+                        BodyLimitError::Overflow { limit, length } => {
+                            JsonRejection::Overflow { limit, length }
+                        }
+                    })?;
 
-        let res = ready!(fut.poll(cx));
+                facet_json::from_slice::<T>(&data)
+                    .map(Json)
+                    .map_err(JsonRejection::Deserialize)
+            };
 
-        let res = match res {
-            Err(err) => Err(JsonRejection::Body(err)),
-            Ok(data) => match facet_json::from_slice::<T>(&data) {
-                Ok(data) => Ok(Json(data)),
-                Err(e) => Err(JsonRejection::Deserialize(e))?,
-            },
-        };
+            extract.await.map_err(|rejection| map_rejection(rejection, &req, &config))
+        })
+    }
+}
 
-        Poll::Ready(res)
+impl<'a, T: Facet<'a>> Json<T> {
+    /// Wrap this responder in a [`CustomizeResponder`](crate::CustomizeResponder)
+    /// so its status code and headers can be overridden while still
+    /// serializing the body through `facet_json`.
+    pub fn customize(self) -> crate::CustomizeResponder<Self> {
+        crate::CustomizeResponder::new(self)
     }
 }
 
@@ -0,0 +1,21 @@
+//! Facet-powered extractors and responders for `actix-web`.
+//!
+//! This crate mirrors the extractors `actix-web` ships around `serde`
+//! (`Json`, `Form`, ...) but deserializes through [`facet`] instead.
+
+mod body;
+mod customize;
+mod form;
+mod json;
+mod multipart;
+mod path;
+mod payload;
+mod query;
+mod urlencode;
+
+pub use customize::CustomizeResponder;
+pub use form::{Form, FormConfig, FormRejection};
+pub use json::{Json, JsonConfig, JsonRejection};
+pub use path::{Path, PathConfig, PathRejection};
+pub use payload::{Payload, PayloadConfig, PayloadRejection};
+pub use query::{Query, QueryRejection};
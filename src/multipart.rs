@@ -0,0 +1,135 @@
+//! Minimal `multipart/form-data` support backing [`crate::Form`].
+//!
+//! This only extracts what the facet deserializer needs: a flat list of
+//! `name -> value` pairs. File parts are handled like any other field: their
+//! raw body, decoded as UTF-8, becomes the value. Only the `name` parameter
+//! of `Content-Disposition` is read; a part's `filename` and `Content-Type`
+//! are neither read nor surfaced.
+//!
+//! Because of that, a part's value must be valid UTF-8 — a binary file part
+//! (an image, a PDF, ...) makes the whole body rejected with
+//! [`FormRejection::InvalidUtf8`]. See [`crate::Form`]'s docs.
+
+use crate::form::FormRejection;
+use crate::urlencode::percent_encode_into;
+
+/// Parses the `boundary` parameter out of a `multipart/form-data`
+/// `Content-Type` header value.
+pub(crate) fn parse_boundary(content_type: &str) -> Result<&str, FormRejection> {
+    content_type
+        .split(';')
+        .map(str::trim)
+        .find_map(|segment| segment.strip_prefix("boundary="))
+        .map(|boundary| boundary.trim_matches('"'))
+        .filter(|boundary| !boundary.is_empty())
+        .ok_or(FormRejection::MissingBoundary)
+}
+
+struct Part {
+    name: String,
+    value: String,
+}
+
+fn parse_part(part: &str) -> Result<Part, FormRejection> {
+    let (headers, value) = part
+        .split_once("\r\n\r\n")
+        .ok_or(FormRejection::MalformedMultipart)?;
+
+    let name = headers
+        .split("\r\n")
+        .find_map(|line| line.strip_prefix("Content-Disposition:"))
+        .and_then(|disposition| {
+            disposition
+                .split(';')
+                .map(str::trim)
+                .find_map(|segment| segment.strip_prefix("name="))
+        })
+        .map(|name| name.trim_matches('"').to_owned())
+        .ok_or(FormRejection::MalformedMultipart)?;
+
+    Ok(Part {
+        name,
+        value: value.trim_end_matches("\r\n").to_owned(),
+    })
+}
+
+/// Splits a `multipart/form-data` body into `name -> value` pairs.
+pub(crate) fn parse_parts(body: &[u8], boundary: &str) -> Result<Vec<(String, String)>, FormRejection> {
+    let body = str::from_utf8(body).map_err(|_| FormRejection::InvalidUtf8)?;
+    let delimiter = format!("--{boundary}");
+
+    body.split(delimiter.as_str())
+        .map(|part| part.trim_matches(['\r', '\n']))
+        .filter(|part| !part.is_empty() && *part != "--")
+        .map(|part| parse_part(part).map(|Part { name, value }| (name, value)))
+        .collect()
+}
+
+/// Re-encodes `name -> value` pairs as a `x-www-form-urlencoded` string so
+/// they can be fed through the same deserializer used for urlencoded bodies.
+pub(crate) fn pairs_to_urlencoded(pairs: &[(String, String)]) -> String {
+    let mut out = String::new();
+
+    for (key, value) in pairs {
+        if !out.is_empty() {
+            out.push('&');
+        }
+        percent_encode_into(&mut out, key);
+        out.push('=');
+        percent_encode_into(&mut out, value);
+    }
+
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_boundary_from_content_type() {
+        let boundary = parse_boundary("multipart/form-data; boundary=abc123").unwrap();
+        assert_eq!(boundary, "abc123");
+    }
+
+    #[test]
+    fn parses_quoted_boundary() {
+        let boundary = parse_boundary(r#"multipart/form-data; boundary="abc 123""#).unwrap();
+        assert_eq!(boundary, "abc 123");
+    }
+
+    #[test]
+    fn rejects_missing_boundary_parameter() {
+        assert!(matches!(
+            parse_boundary("multipart/form-data"),
+            Err(FormRejection::MissingBoundary)
+        ));
+    }
+
+    #[test]
+    fn rejects_empty_boundary_parameter() {
+        assert!(matches!(
+            parse_boundary("multipart/form-data; boundary="),
+            Err(FormRejection::MissingBoundary)
+        ));
+    }
+
+    #[test]
+    fn parses_parts_separated_by_the_trailing_delimiter() {
+        let body = b"--B\r\nContent-Disposition: form-data; name=\"a\"\r\n\r\n1\r\n--B--\r\n";
+
+        let pairs = parse_parts(body, "B").unwrap();
+
+        assert_eq!(pairs, vec![("a".to_owned(), "1".to_owned())]);
+    }
+
+    #[test]
+    fn rejects_non_utf8_part_bodies() {
+        let body = b"--B\r\nContent-Disposition: form-data; name=\"a\"\r\n\r\n\xFF\r\n--B--\r\n";
+
+        assert!(matches!(
+            parse_parts(body, "B"),
+            Err(FormRejection::InvalidUtf8)
+        ));
+    }
+}
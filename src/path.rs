@@ -0,0 +1,148 @@
+use core::fmt;
+use std::{ops, rc::Rc};
+
+use actix_web::{FromRequest, HttpRequest, ResponseError, http::StatusCode};
+use facet::Facet;
+
+use crate::urlencode::percent_encode_into;
+
+/// Extracts typed values out of the request's matched URL path segments,
+/// deserialized through `facet_urlencoded`.
+///
+/// `T` can be a struct with named fields (optionally `#[facet(rename)]`d to
+/// match a differently-spelled route segment), matched by the route's
+/// `{name}` captures, or a tuple like `(u32, String)`, matched positionally
+/// by the captures' order in the route pattern (`req.match_info()` preserves
+/// that order even though it only carries names).
+#[derive(Debug, facet::Facet)]
+#[facet(transparent)]
+pub struct Path<T>(pub T);
+
+impl<T> Path<T> {
+    /// Unwrap into inner `T` value.
+    pub fn into_inner(self) -> T {
+        self.0
+    }
+}
+
+impl<T> ops::Deref for Path<T> {
+    type Target = T;
+
+    fn deref(&self) -> &T {
+        &self.0
+    }
+}
+
+impl<T> ops::DerefMut for Path<T> {
+    fn deref_mut(&mut self) -> &mut T {
+        &mut self.0
+    }
+}
+
+impl<T: fmt::Display> fmt::Display for Path<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Display::fmt(&self.0, f)
+    }
+}
+
+/// Extractor configuration for [`Path`].
+///
+/// Register one with `App::app_data`/`Resource::app_data` to take control of
+/// how a [`PathRejection`] is turned into an `actix_web::Error` — for
+/// instance to answer with `404 Not Found` instead of the default `400 Bad
+/// Request` when a route segment doesn't deserialize:
+///
+/// ```ignore
+/// App::new().app_data(
+///     PathConfig::default().error_handler(|_, _| actix_web::error::ErrorNotFound("not found")),
+/// )
+/// ```
+#[derive(Clone, Default)]
+pub struct PathConfig {
+    error_handler: Option<Rc<dyn Fn(PathRejection, &HttpRequest) -> actix_web::Error>>,
+}
+
+impl PathConfig {
+    /// Override how a [`PathRejection`] is turned into the `actix_web::Error`
+    /// returned to the caller.
+    pub fn error_handler<F>(mut self, handler: F) -> Self
+    where
+        F: Fn(PathRejection, &HttpRequest) -> actix_web::Error + 'static,
+    {
+        self.error_handler = Some(Rc::new(handler));
+        self
+    }
+}
+
+#[derive(Debug)]
+pub enum PathRejection {
+    /// Failed to deserialize the matched URL segments.
+    Deserialize(facet_urlencoded::UrlEncodedError),
+}
+
+impl fmt::Display for PathRejection {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            PathRejection::Deserialize(err) => {
+                write!(f, "Failed to deserialize path segments: {err}")
+            }
+        }
+    }
+}
+
+impl ResponseError for PathRejection {
+    fn status_code(&self) -> StatusCode {
+        match self {
+            PathRejection::Deserialize(_deserialize_error) => StatusCode::BAD_REQUEST,
+        }
+    }
+}
+
+/// Rebuilds an urlencoded-like string out of the request's matched URL
+/// segments so it can be fed through the same `facet_urlencoded` deserializer
+/// used by [`crate::Query`] and [`crate::Form`].
+///
+/// Each segment is encoded under both its route-declared name (so a
+/// named-field struct can pick it up by name) and its positional index in
+/// pattern order (so a tuple can pick it up by position) — `match_info()`
+/// has no other way to expose "the Nth segment", since it's keyed by name.
+fn match_info_to_urlencoded(req: &HttpRequest) -> String {
+    let mut out = String::new();
+
+    for (index, (key, value)) in req.match_info().iter().enumerate() {
+        if !out.is_empty() {
+            out.push('&');
+        }
+        percent_encode_into(&mut out, key);
+        out.push('=');
+        percent_encode_into(&mut out, value);
+
+        out.push('&');
+        out.push_str(&index.to_string());
+        out.push('=');
+        percent_encode_into(&mut out, value);
+    }
+
+    out
+}
+
+impl<T: Facet<'static>> FromRequest for Path<T> {
+    type Error = actix_web::Error;
+    type Future = std::future::Ready<Result<Self, actix_web::Error>>;
+
+    fn from_request(req: &HttpRequest, _payload: &mut actix_web::dev::Payload) -> Self::Future {
+        let config = req.app_data::<PathConfig>().cloned().unwrap_or_default();
+
+        let encoded = match_info_to_urlencoded(req);
+
+        let res = facet_urlencoded::from_str_owned::<T>(&encoded)
+            .map(Path)
+            .map_err(PathRejection::Deserialize)
+            .map_err(|rejection| match &config.error_handler {
+                Some(handler) => handler(rejection, req),
+                None => rejection.into(),
+            });
+
+        std::future::ready(res)
+    }
+}
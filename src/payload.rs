@@ -0,0 +1,266 @@
+use core::fmt;
+use std::{future::Future, ops, pin::Pin, rc::Rc};
+
+use actix_web::{
+    FromRequest, HttpRequest, ResponseError,
+    http::{
+        StatusCode,
+        header::{CONTENT_LENGTH, CONTENT_TYPE},
+    },
+    mime,
+};
+use facet::Facet;
+
+use crate::body::{self, BodyLimitError};
+
+/// A boxed, pinned future, used instead of a hand-rolled `Future` impl so
+/// extraction can just be written as a straight-line `async` block.
+type BoxFuture<T> = Pin<Box<dyn Future<Output = T>>>;
+
+/// A body extractor that negotiates the request format instead of hard-coding
+/// it: `application/json` is deserialized the same way [`crate::Json`] does,
+/// and `application/x-www-form-urlencoded` the same way [`crate::Form`] does.
+#[derive(Debug, facet::Facet)]
+#[facet(transparent)]
+pub struct Payload<T>(pub T);
+
+impl<T> Payload<T> {
+    /// Unwrap into inner `T` value.
+    pub fn into_inner(self) -> T {
+        self.0
+    }
+}
+
+impl<T> ops::Deref for Payload<T> {
+    type Target = T;
+
+    fn deref(&self) -> &T {
+        &self.0
+    }
+}
+
+impl<T> ops::DerefMut for Payload<T> {
+    fn deref_mut(&mut self) -> &mut T {
+        &mut self.0
+    }
+}
+
+impl<T: fmt::Display> fmt::Display for Payload<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Display::fmt(&self.0, f)
+    }
+}
+
+/// Default body size limit applied to [`Payload`] extraction when no
+/// [`PayloadConfig`] is registered (2 MiB).
+const DEFAULT_LIMIT: usize = 2_097_152;
+
+/// Extractor configuration for [`Payload`].
+///
+/// Register one with `App::app_data`/`Resource::app_data` to override the
+/// default body size limit or take full control of how rejections are
+/// turned into an `actix_web::Error`:
+///
+/// ```ignore
+/// App::new().app_data(PayloadConfig::default().limit(4096))
+/// ```
+#[derive(Clone)]
+pub struct PayloadConfig {
+    limit: usize,
+    error_handler: Option<Rc<dyn Fn(PayloadRejection, &HttpRequest) -> actix_web::Error>>,
+}
+
+impl PayloadConfig {
+    /// Set the maximum allowed body size, in bytes.
+    pub fn limit(mut self, limit: usize) -> Self {
+        self.limit = limit;
+        self
+    }
+
+    /// Override how a [`PayloadRejection`] is turned into the
+    /// `actix_web::Error` returned to the caller.
+    pub fn error_handler<F>(mut self, handler: F) -> Self
+    where
+        F: Fn(PayloadRejection, &HttpRequest) -> actix_web::Error + 'static,
+    {
+        self.error_handler = Some(Rc::new(handler));
+        self
+    }
+}
+
+impl Default for PayloadConfig {
+    fn default() -> Self {
+        PayloadConfig {
+            limit: DEFAULT_LIMIT,
+            error_handler: None,
+        }
+    }
+}
+
+#[derive(Debug)]
+pub enum PayloadRejection {
+    /// Failed to read the request body.
+    Body(actix_web::Error),
+    /// Failed to deserialize a JSON body.
+    Json(facet_json::DeserializeError),
+    /// Failed to deserialize a urlencoded body.
+    Form(facet_urlencoded::UrlEncodedError),
+    /// Missing `Content-Type` header.
+    MissingContentType,
+    /// `Content-Type` is neither `application/json` nor
+    /// `application/x-www-form-urlencoded`.
+    UnsupportedContentType,
+    /// The urlencoded body is not valid UTF-8.
+    InvalidUtf8,
+    /// The body (or its announced `Content-Length`) exceeds the configured
+    /// [`PayloadConfig::limit`].
+    Overflow { limit: usize, length: usize },
+}
+
+impl fmt::Display for PayloadRejection {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            PayloadRejection::Body(err) => {
+                write!(f, "Failed to read request body: {err}")
+            }
+            PayloadRejection::Json(err) => {
+                write!(f, "Failed to deserialize JSON: {err}")
+            }
+            PayloadRejection::Form(err) => {
+                write!(f, "Failed to deserialize form: {err}")
+            }
+            PayloadRejection::MissingContentType => {
+                write!(f, "Missing `Content-Type` header")
+            }
+            PayloadRejection::UnsupportedContentType => {
+                write!(
+                    f,
+                    "Unsupported `Content-Type`: expected `application/json` or `application/x-www-form-urlencoded`"
+                )
+            }
+            PayloadRejection::InvalidUtf8 => {
+                write!(f, "Payload body is not valid UTF-8")
+            }
+            PayloadRejection::Overflow { limit, length } => {
+                write!(f, "Payload body ({length} bytes) is larger than allowed (limit: {limit} bytes)")
+            }
+        }
+    }
+}
+
+impl ResponseError for PayloadRejection {
+    fn status_code(&self) -> StatusCode {
+        match self {
+            PayloadRejection::Body(_error) => StatusCode::BAD_REQUEST,
+            PayloadRejection::Json(_) | PayloadRejection::Form(_) => {
+                StatusCode::UNPROCESSABLE_ENTITY
+            }
+            PayloadRejection::MissingContentType | PayloadRejection::UnsupportedContentType => {
+                StatusCode::UNSUPPORTED_MEDIA_TYPE
+            }
+            PayloadRejection::InvalidUtf8 => StatusCode::BAD_REQUEST,
+            PayloadRejection::Overflow { .. } => StatusCode::PAYLOAD_TOO_LARGE,
+        }
+    }
+}
+
+#[derive(Clone, Copy)]
+enum PayloadFormat {
+    Json,
+    Form,
+}
+
+fn detect_format(req: &HttpRequest) -> Result<PayloadFormat, PayloadRejection> {
+    let ct = req
+        .headers()
+        .get(CONTENT_TYPE)
+        .ok_or(PayloadRejection::MissingContentType)?;
+
+    let mime: mime::Mime = ct
+        .to_str()
+        .ok()
+        .and_then(|ct| ct.parse().ok())
+        .ok_or(PayloadRejection::UnsupportedContentType)?;
+
+    match (mime.type_(), mime.subtype()) {
+        (mime::APPLICATION, mime::JSON) => Ok(PayloadFormat::Json),
+        (mime::APPLICATION, mime::WWW_FORM_URLENCODED) => Ok(PayloadFormat::Form),
+        _ => Err(PayloadRejection::UnsupportedContentType),
+    }
+}
+
+fn check_announced_length(
+    req: &HttpRequest,
+    config: &PayloadConfig,
+) -> Result<(), PayloadRejection> {
+    let Some(length) = req
+        .headers()
+        .get(CONTENT_LENGTH)
+        .and_then(|len| len.to_str().ok())
+        .and_then(|len| len.parse::<usize>().ok())
+    else {
+        return Ok(());
+    };
+
+    if length > config.limit {
+        Err(PayloadRejection::Overflow {
+            limit: config.limit,
+            length,
+        })
+    } else {
+        Ok(())
+    }
+}
+
+fn map_rejection(
+    rejection: PayloadRejection,
+    req: &HttpRequest,
+    config: &PayloadConfig,
+) -> actix_web::Error {
+    match &config.error_handler {
+        Some(handler) => handler(rejection, req),
+        None => rejection.into(),
+    }
+}
+
+impl<T: Facet<'static>> FromRequest for Payload<T> {
+    type Error = actix_web::Error;
+    type Future = BoxFuture<Result<Payload<T>, actix_web::Error>>;
+
+    fn from_request(req: &HttpRequest, payload: &mut actix_web::dev::Payload) -> Self::Future {
+        let req = req.clone();
+        let config = req.app_data::<PayloadConfig>().cloned().unwrap_or_default();
+        let payload = payload.take();
+
+        Box::pin(async move {
+            let extract = async {
+                let format = detect_format(&req)?;
+                check_announced_length(&req, &config)?;
+
+                let data = body::collect_limited(payload, config.limit)
+                    .await
+                    .map_err(|err| match err {
+                        BodyLimitError::Payload(err) => PayloadRejection::Body(err.into()),
+                        BodyLimitError::Overflow { limit, length } => {
+                            PayloadRejection::Overflow { limit, length }
+                        }
+                    })?;
+
+                match format {
+                    PayloadFormat::Json => facet_json::from_slice::<T>(&data)
+                        .map(Payload)
+                        .map_err(PayloadRejection::Json),
+                    PayloadFormat::Form => str::from_utf8(data.as_ref())
+                        .map_err(|_| PayloadRejection::InvalidUtf8)
+                        .and_then(|data| {
+                            facet_urlencoded::from_str_owned::<T>(data)
+                                .map_err(PayloadRejection::Form)
+                        })
+                        .map(Payload),
+                }
+            };
+
+            extract.await.map_err(|rejection| map_rejection(rejection, &req, &config))
+        })
+    }
+}
@@ -0,0 +1,78 @@
+use core::fmt;
+use std::{future::Ready, ops};
+
+use actix_web::{FromRequest, HttpRequest, ResponseError, http::StatusCode};
+use facet::Facet;
+
+#[derive(Debug, facet::Facet)]
+#[facet(transparent)]
+pub struct Query<T>(pub T);
+
+impl<T> Query<T> {
+    /// Unwrap into inner `T` value.
+    pub fn into_inner(self) -> T {
+        self.0
+    }
+}
+
+impl<T> ops::Deref for Query<T> {
+    type Target = T;
+
+    fn deref(&self) -> &T {
+        &self.0
+    }
+}
+
+impl<T> ops::DerefMut for Query<T> {
+    fn deref_mut(&mut self) -> &mut T {
+        &mut self.0
+    }
+}
+
+impl<T: fmt::Display> fmt::Display for Query<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Display::fmt(&self.0, f)
+    }
+}
+
+#[derive(Debug)]
+pub enum QueryRejection {
+    /// Failed to deserialize the query string.
+    Deserialize(facet_urlencoded::UrlEncodedError),
+}
+
+impl fmt::Display for QueryRejection {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            QueryRejection::Deserialize(err) => {
+                write!(f, "Failed to deserialize query string: {err}")
+            }
+        }
+    }
+}
+
+impl ResponseError for QueryRejection {
+    // Every deserialize failure answers 422, whether it is a structural parse
+    // failure or a `#[facet(invariants = ...)]` failure; no 400 is returned.
+    // `facet_urlencoded::UrlEncodedError` exposes no typed way to tell the two
+    // apart, only a `Display` string. This matches `JsonRejection`'s and
+    // `FormRejection`'s own `Deserialize` handling.
+    fn status_code(&self) -> StatusCode {
+        match self {
+            QueryRejection::Deserialize(_) => StatusCode::UNPROCESSABLE_ENTITY,
+        }
+    }
+}
+
+impl<T: Facet<'static>> FromRequest for Query<T> {
+    type Error = QueryRejection;
+    type Future = Ready<Result<Self, QueryRejection>>;
+
+    fn from_request(req: &HttpRequest, _payload: &mut actix_web::dev::Payload) -> Self::Future {
+        let res = facet_urlencoded::from_str_owned::<T>(req.query_string())
+            .map(Query)
+            .map_err(QueryRejection::Deserialize);
+
+        std::future::ready(res)
+    }
+}
@@ -0,0 +1,38 @@
+//! Shared percent-encoding helper used to rebuild `key=value&...` strings fed
+//! back through `facet_urlencoded`'s deserializer — by [`crate::path`] for
+//! matched URL segments and by [`crate::multipart`] for multipart field
+//! values.
+
+/// Percent-encodes the bytes that would otherwise be misread once the
+/// segment is spliced back into a urlencoded string: `&` and `=` are
+/// delimiter syntax, `%` would be read as the start of an escape, and `+`
+/// (along with a literal space) is decoded back to a space by
+/// `facet_urlencoded` — left unescaped, a value like `"2+2"` would silently
+/// come back as `"2 2"`.
+pub(crate) fn percent_encode_into(out: &mut String, segment: &str) {
+    for ch in segment.chars() {
+        match ch {
+            '&' | '=' | '%' | '+' | ' ' => out.push_str(&format!("%{:02X}", ch as u32)),
+            _ => out.push(ch),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn escapes_reserved_and_plus_and_space() {
+        let mut out = String::new();
+        percent_encode_into(&mut out, "a+b=c&d%e f");
+        assert_eq!(out, "a%2Bb%3Dc%26d%25e%20f");
+    }
+
+    #[test]
+    fn leaves_ordinary_characters_untouched() {
+        let mut out = String::new();
+        percent_encode_into(&mut out, "hello-world_123");
+        assert_eq!(out, "hello-world_123");
+    }
+}